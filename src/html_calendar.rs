@@ -0,0 +1,182 @@
+/*
+ * Copyright (c) 2024. All rights reserved.
+ * This software is the confidential and proprietary information of Louis Travaux ("Confidential Information").
+ * You shall not disclose such Confidential Information and shall use it only in accordance with the terms of the license agreement you entered into with Louis Travaux.
+ */
+
+//! Self-contained HTML week view generated alongside `ut1.ics`.
+//!
+//! Renders the merged events into a 7-day grid spanning 07:00–21:00 (the same
+//! bounds [`crate::convert_events`] uses), each event a coloured block. A
+//! [`PrivacyMode`] (env `PRIVACY_MODE`) decides whether full details or a
+//! neutral "Occupé" label are shown, so the page can be published without
+//! leaking the owner's schedule.
+
+use std::fmt::Write as _;
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, Timelike, Weekday};
+
+use crate::PlanningEvent;
+
+/// Output file written next to `ut1.ics`.
+pub const HTML_FILE: &str = "ut1.html";
+
+/// First and last hour displayed on the grid (inclusive start, exclusive end).
+const DAY_START_HOUR: u32 = 7;
+const DAY_END_HOUR: u32 = 21;
+
+/// How much of each event to reveal in the rendered page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrivacyMode {
+    /// Show `cours`, `salle`, `prof` and notes.
+    Private,
+    /// Replace every detail with a neutral "Occupé" label.
+    Public,
+}
+
+impl PrivacyMode {
+    /// Reads the mode from `PRIVACY_MODE` (`public`/`private`), defaulting to
+    /// [`PrivacyMode::Private`].
+    pub fn from_env() -> Self {
+        match std::env::var("PRIVACY_MODE") {
+            Ok(v) if v.eq_ignore_ascii_case("public") => PrivacyMode::Public,
+            _ => PrivacyMode::Private,
+        }
+    }
+}
+
+/// Renders the events into a standalone HTML document and writes it to
+/// [`HTML_FILE`].
+pub fn generate(events: &[PlanningEvent]) -> Result<()> {
+    let html = render(events, PrivacyMode::from_env());
+    std::fs::write(HTML_FILE, html).context("Failed to write HTML calendar")?;
+    Ok(())
+}
+
+/// Renders the events into a standalone HTML document.
+pub fn render(events: &[PlanningEvent], mode: PrivacyMode) -> String {
+    let span_hours = (DAY_END_HOUR - DAY_START_HOUR) as f32;
+
+    let mut blocks = String::new();
+    for event in events {
+        let column = event.start.weekday().num_days_from_monday();
+        if column > 6 {
+            continue;
+        }
+
+        // vertical placement as a percentage of the visible day
+        let start_hours = event.start.hour() as f32 + event.start.minute() as f32 / 60.0;
+        let duration_hours = event.duration_s.num_minutes() as f32 / 60.0;
+        let top = ((start_hours - DAY_START_HOUR as f32) / span_hours * 100.0).max(0.0);
+        let height = (duration_hours / span_hours * 100.0).min(100.0 - top);
+
+        let left = column as f32 / 7.0 * 100.0;
+        let color = course_color(&event.cours);
+
+        let (label, title) = match mode {
+            PrivacyMode::Private => (
+                format!(
+                    "<strong>{}</strong><span>{}</span><span>{}</span>",
+                    escape(&event.cours),
+                    escape(&event.salle),
+                    escape(&event.prof),
+                ),
+                escape(&event.notes),
+            ),
+            PrivacyMode::Public => ("<strong>Occupé</strong>".to_string(), String::new()),
+        };
+
+        let _ = write!(
+            blocks,
+            r#"<div class="event" style="left:{left:.4}%;top:{top:.4}%;height:{height:.4}%;background:{color};" title="{title}">{label}</div>"#,
+        );
+    }
+
+    let mut day_headers = String::new();
+    for day in [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ] {
+        let _ = write!(day_headers, "<div class=\"day\">{}</div>", day_name(day));
+    }
+
+    let mut hour_rows = String::new();
+    for hour in DAY_START_HOUR..DAY_END_HOUR {
+        let _ = write!(hour_rows, "<div class=\"hour\">{:02}:00</div>", hour);
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="fr">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>UT1 — Emploi du temps</title>
+<style>
+  body {{ margin: 0; font-family: system-ui, sans-serif; background: #f7f7f9; color: #222; }}
+  .calendar {{ display: grid; grid-template-columns: 3rem 1fr; max-width: 1100px; margin: 1rem auto; }}
+  .days {{ grid-column: 2; display: grid; grid-template-columns: repeat(7, 1fr); font-weight: 600; }}
+  .day {{ padding: .4rem; text-align: center; border-bottom: 1px solid #ddd; }}
+  .hours {{ grid-column: 1; display: flex; flex-direction: column; }}
+  .hour {{ flex: 1; font-size: .7rem; color: #888; text-align: right; padding-right: .3rem; }}
+  .grid {{ grid-column: 2; position: relative; height: {grid_height}px; border-left: 1px solid #ddd; }}
+  .grid::before {{ content: ""; position: absolute; inset: 0;
+    background-image: repeating-linear-gradient(to right, #eee 0 1px, transparent 1px calc(100%/7)),
+                      repeating-linear-gradient(to bottom, #eee 0 1px, transparent 1px calc(100%/{hours})); }}
+  .event {{ position: absolute; width: calc(100%/7 - 4px); margin-left: 2px; border-radius: 6px;
+    padding: 2px 4px; box-sizing: border-box; overflow: hidden; font-size: .7rem; color: #fff;
+    display: flex; flex-direction: column; }}
+  .event span {{ opacity: .9; }}
+</style>
+</head>
+<body>
+<div class="calendar">
+  <div style="grid-column: 1;"></div>
+  <div class="days">{day_headers}</div>
+  <div class="hours">{hour_rows}</div>
+  <div class="grid">{blocks}</div>
+</div>
+</body>
+</html>
+"#,
+        grid_height = (DAY_END_HOUR - DAY_START_HOUR) * 48,
+        hours = DAY_END_HOUR - DAY_START_HOUR,
+        day_headers = day_headers,
+        hour_rows = hour_rows,
+        blocks = blocks,
+    )
+}
+
+/// Deterministic colour per course name so the same course keeps the
+/// same block colour across renders.
+fn course_color(cours: &str) -> String {
+    let hue = cours.bytes().fold(0u32, |acc, b| acc.wrapping_add(b as u32)) % 360;
+    format!("hsl({}, 55%, 45%)", hue)
+}
+
+fn day_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Lundi",
+        Weekday::Tue => "Mardi",
+        Weekday::Wed => "Mercredi",
+        Weekday::Thu => "Jeudi",
+        Weekday::Fri => "Vendredi",
+        Weekday::Sat => "Samedi",
+        Weekday::Sun => "Dimanche",
+    }
+}
+
+/// Minimal HTML-attribute/text escaping.
+fn escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
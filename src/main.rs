@@ -6,10 +6,12 @@
 
 use headless_chrome::{Browser, LaunchOptions};
 use std::{
+    collections::{BTreeMap, HashMap, HashSet},
     env::var,
     sync::Arc,
 };
-use chrono::{Datelike, Duration, Utc, Weekday};
+use chrono::{DateTime, Datelike, Duration, LocalResult, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
 use dotenv::dotenv;
 use headless_chrome::{
     browser::{
@@ -29,18 +31,39 @@ use headless_chrome::browser::tab::{
     RequestPausedDecision,
 };
 
-use ics::{Event, ICalendar, properties::{
+#[cfg(feature = "serve")]
+mod server;
+
+mod html_calendar;
+
+use ics::{Event, ICalendar, TimeZone as IcsTimeZone, Standard, Daylight, parameters, properties::{
     Summary,
     Location,
     Organizer,
-    Description,
+    Attendee,
+    Comment,
+    Categories,
     DtStart,
     DtEnd,
+    RRule,
+    ExDate,
+    Sequence,
+    TzName,
 }};
-use rand::random;
 use anyhow::{Result, anyhow, Context};
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tokio::task::JoinHandle;
 
+/// File used to remember each event's content hash, sequence number and
+/// `DTSTAMP` between runs so the subscribed calendar diffs cleanly instead of
+/// churning every event on each 6-hour scrape.
+const STATE_FILE: &str = "ut1.state.json";
+
+/// Calendar address identifying the scraper/service as the VEVENT organizer.
+const ORGANIZER_CAL_ADDRESS: &str = "mailto:ut1-timetable@ut1-timetable";
+
 #[derive(Debug, Clone)]
 pub struct PlanningEvent {
     pub start: chrono::naive::NaiveDateTime,
@@ -51,6 +74,39 @@ pub struct PlanningEvent {
     pub notes: String,
 }
 
+/// A single calendar entry ready for ICS generation.
+///
+/// Most lectures repeat at the same slot across the scraped weeks, so instead
+/// of one standalone `VEVENT` per occurrence we fold those into a single
+/// recurring entry (see [`fold_recurring_events`]). `recurrence` is `None` for
+/// genuine one-offs.
+#[derive(Debug, Clone)]
+pub struct CalendarEntry {
+    pub event: PlanningEvent,
+    pub recurrence: Option<Recurrence>,
+}
+
+/// Weekly recurrence metadata for a folded [`CalendarEntry`].
+#[derive(Debug, Clone)]
+pub struct Recurrence {
+    /// End of the last occurrence (`RRULE ... UNTIL`).
+    pub until: chrono::naive::NaiveDateTime,
+    /// Start datetimes of weeks that are expected but were not scraped
+    /// (holidays, cancellations) — emitted as `EXDATE` lines.
+    pub exdates: Vec<chrono::naive::NaiveDateTime>,
+}
+
+/// Per-event sync state persisted in [`STATE_FILE`] across runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventState {
+    /// Hash of the event's user-visible content (summary, room, prof, times…).
+    pub content_hash: String,
+    /// RFC5545 `SEQUENCE`, bumped only when `content_hash` changes.
+    pub sequence: u32,
+    /// `DTSTAMP` of the last revision, reused verbatim while content is stable.
+    pub dtstamp: String,
+}
+
 struct CssInterceptor;
 
 impl RequestInterceptor for CssInterceptor {
@@ -78,24 +134,88 @@ impl RequestInterceptor for CssInterceptor {
     }
 }
 
+/// Login entry point; the planning page is reached through the CAS redirect.
+const PLANNING_URL: &str = "https://cas.ut-capitole.fr/cas/login?service=https%3A%2F%2Fade-production.ut-capitole.fr%2Fdirect%2Fmyplanning.jsp";
+
+#[derive(Parser)]
+#[command(name = "ut1-timetable", about = "Scrape and export the UT1 Capitole timetable")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Scrape every 6 hours and deploy the calendar (default when no command).
+    Watch,
+    /// Scrape once and export a single ISO week (or `start-end` range) to ICS.
+    Export {
+        /// ISO week number, or an inclusive range such as `36-40`.
+        #[arg(long)]
+        week: String,
+        /// Output ICS filename.
+        #[arg(long, short, default_value = "ut1.ics")]
+        output: String,
+    },
+    /// Print a markdown summary of a week's events to stdout (no ICS produced).
+    Describe {
+        /// ISO week number (defaults to the current week).
+        week: Option<u32>,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv().ok();
 
-    let url = "https://cas.ut-capitole.fr/cas/login?service=https%3A%2F%2Fade-production.ut-capitole.fr%2Fdirect%2Fmyplanning.jsp";
+    match Cli::parse().command.unwrap_or(Command::Watch) {
+        Command::Watch => watch().await,
+        Command::Export { week, output } => export(&week, &output).await,
+        Command::Describe { week } => describe(week).await,
+    }
+}
+
+/// Preserves the historical behaviour: scrape every 6 hours, regenerate the
+/// calendar and HTML view, and deploy them.
+async fn watch() -> Result<()> {
+    // when built with the `serve` feature and SERVE_ADDR is set, keep the
+    // latest calendar in memory and expose it over HTTP/CalDAV
+    #[cfg(feature = "serve")]
+    let served = {
+        let served = server::shared();
+        if let Ok(addr) = var("SERVE_ADDR") {
+            let addr = addr.parse().context("Failed to parse 'SERVE_ADDR'")?;
+            let served = served.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server::serve(served, addr).await {
+                    println!("INFO: serve error: {}", e);
+                }
+            });
+        }
+        served
+    };
 
     // scrape planning every 6 hours
     loop {
         let current_millis = chrono::Local::now().timestamp_millis();
 
         // Connect to planning and scrape events
-        match scrape_ut1_planning(url).await {
+        match scrape_ut1_planning(PLANNING_URL).await {
             Ok(events) => {
                 let new_millis = chrono::Local::now().timestamp_millis();
                 println!("Scraping took {} ms", new_millis - current_millis);
 
                 // Convert events to ics file
-                create_ics_from_planning_event_vec(&events).await?;
+                create_ics_from_planning_event_vec(&events, "ut1.ics").await?;
+
+                // render the human-readable HTML week view alongside it
+                html_calendar::generate(&events)?;
+
+                // refresh the in-memory served calendar in place
+                #[cfg(feature = "serve")]
+                if let Ok(body) = std::fs::read_to_string("ut1.ics") {
+                    server::update(&served, body).await;
+                }
 
                 // deploy ics file
                 deploy_ics_file().await?;
@@ -108,7 +228,95 @@ async fn main() -> Result<()> {
         };
 
     }
+}
 
+/// One-shot export of the requested ISO week (or range) to an ICS file.
+async fn export(week: &str, output: &str) -> Result<()> {
+    let weeks = parse_week_arg(week)?;
+    let events = scrape_week_events(&weeks).await?;
+    create_ics_from_planning_event_vec(&events, output).await?;
+    println!("Exported {} events to {}", events.len(), output);
+    Ok(())
+}
+
+/// Prints a markdown summary of a week's events to stdout without producing an
+/// ICS file.
+async fn describe(week: Option<u32>) -> Result<()> {
+    let week = week.unwrap_or_else(|| chrono::Local::now().iso_week().week());
+    let mut events = scrape_week_events(&[week]).await?;
+    events.sort_by_key(|e| e.start);
+
+    println!("# Semaine {}\n", week);
+    if events.is_empty() {
+        println!("_Aucun cours._");
+        return Ok(());
+    }
+    for event in &events {
+        let end = event.start + event.duration_s;
+        println!(
+            "- **{}** {}–{} · {} · {}",
+            event.start.format("%a %d/%m"),
+            event.start.format("%H:%M"),
+            end.format("%H:%M"),
+            event.cours,
+            event.salle,
+        );
+    }
+    Ok(())
+}
+
+/// Parses the `--week` argument: a single ISO week (`36`) or an inclusive range
+/// (`36-40`).
+fn parse_week_arg(raw: &str) -> Result<Vec<u32>> {
+    match raw.split_once('-') {
+        Some((start, end)) => {
+            let start: u32 = start.trim().parse().context("Invalid start week")?;
+            let end: u32 = end.trim().parse().context("Invalid end week")?;
+            if end < start {
+                return Err(anyhow!("Week range end is before its start"));
+            }
+            Ok((start..=end).collect())
+        }
+        None => Ok(vec![raw.trim().parse().context("Invalid week number")?]),
+    }
+}
+
+/// Scrapes the planning and keeps only the events falling in the requested ISO
+/// weeks.
+///
+/// The scraper navigates forward from the current week, covering
+/// `NB_WEEKS_TO_SCRAPE` weeks. A requested week outside that window can't be
+/// reached from the grid, so rather than filter it down to a silently empty
+/// result (which `export` would report as a successful 0-event file) we fail
+/// loudly and tell the caller which weeks are reachable.
+async fn scrape_week_events(weeks: &[u32]) -> Result<Vec<PlanningEvent>> {
+    let horizon = var("NB_WEEKS_TO_SCRAPE")?
+        .parse::<u32>()
+        .context("Failed to parse 'NB_WEEKS_TO_SCRAPE'")?;
+    // step week by week from today so the window wraps correctly across the
+    // year boundary (week 52 → week 1) instead of overflowing the week number.
+    let mut reachable: HashSet<u32> = HashSet::new();
+    let mut cursor = chrono::Local::now().date_naive();
+    for _ in 0..horizon {
+        reachable.insert(cursor.iso_week().week());
+        cursor += Duration::weeks(1);
+    }
+    if let Some(&unreachable) = weeks.iter().find(|&&w| !reachable.contains(&w)) {
+        let mut reachable: Vec<u32> = reachable.into_iter().collect();
+        reachable.sort_unstable();
+        return Err(anyhow!(
+            "Week {} is outside the scrapable window (reachable weeks: {:?}); \
+             increase NB_WEEKS_TO_SCRAPE or request a nearer week",
+            unreachable,
+            reachable,
+        ));
+    }
+
+    let events = scrape_ut1_planning(PLANNING_URL).await?;
+    Ok(events
+        .into_iter()
+        .filter(|e| weeks.contains(&e.start.iso_week().week()))
+        .collect())
 }
 
 async fn scrape_ut1_planning(url: &str) -> Result<Vec<PlanningEvent>> {
@@ -374,10 +582,11 @@ async fn convert_events(
     // calculate days overflow if event is in next week
     let week_overflow = (week - chrono::Local::now().iso_week().week()) * 7;
     // get start date of event (monday 7 am + x days + y half hours)
+    // the grid is wall-clock time in the configured timezone; the UTC instant
+    // is resolved later in `local_to_utc`, so no fixed offset is applied here.
     let start = date
         + Duration::days(x as i64 / day_in_px as i64)
         + Duration::minutes((y as i64 / half_hour_in_px as i64) * 30)
-        - Duration::hours(1)                    // -1 hour because of timezone
         + Duration::days(week_overflow as i64); // + weeks if event is in next week
     // get duration of event (event.height in px / half hours in px * 30 minutes)
     let duration_s = Duration::minutes((height as i64 / half_hour_in_px as i64) * 30);
@@ -385,69 +594,442 @@ async fn convert_events(
     Ok((start, duration_s))
 }
 
-async fn create_ics_from_planning_event_vec(events: &Vec<PlanningEvent>) -> Result<&str> {
+/// Groups scraped occurrences by their stable weekly identity and collapses
+/// runs that repeat across the scraped ISO weeks into a single recurring
+/// [`CalendarEntry`].
+///
+/// The grouping key is (`cours`, `prof`, `salle`, weekday, start time-of-day,
+/// `duration_s`). Within a group the earliest occurrence becomes the entry's
+/// `DtStart`; the latest occurrence's end becomes `RRULE ... UNTIL`. Weeks that
+/// fall between the first and last occurrence but were never scraped are kept
+/// as `EXDATE` lines so holidays and cancellations stay accurate. Groups with a
+/// single occurrence stay plain one-off events.
+fn fold_recurring_events(events: &[PlanningEvent]) -> Vec<CalendarEntry> {
+    // BTreeMap keeps the output order stable across runs.
+    let mut groups: BTreeMap<String, Vec<PlanningEvent>> = BTreeMap::new();
+    for event in events {
+        let key = format!(
+            "{}|{}|{}|{}|{}|{}",
+            event.cours,
+            event.prof,
+            event.salle,
+            event.start.weekday(),
+            event.start.time(),
+            event.duration_s.num_seconds(),
+        );
+        groups.entry(key).or_default().push(event.clone());
+    }
+
+    let mut entries = Vec::new();
+    for (_, mut occurrences) in groups {
+        occurrences.sort_by_key(|e| e.start);
+
+        // one-off: nothing to fold
+        if occurrences.len() < 2 {
+            entries.push(CalendarEntry { event: occurrences.remove(0), recurrence: None });
+            continue;
+        }
+
+        let first = occurrences.first().unwrap().clone();
+        let last = occurrences.last().unwrap();
+        let until = last.start + last.duration_s;
+
+        // collect the starts we actually scraped, then walk the expected weekly
+        // grid between first and last to find the missing ones (EXDATE).
+        let present: HashSet<chrono::naive::NaiveDateTime> =
+            occurrences.iter().map(|e| e.start).collect();
+        let mut exdates = Vec::new();
+        let mut expected = first.start + Duration::weeks(1);
+        while expected < last.start {
+            if !present.contains(&expected) {
+                exdates.push(expected);
+            }
+            expected += Duration::weeks(1);
+        }
+
+        entries.push(CalendarEntry {
+            event: first,
+            recurrence: Some(Recurrence { until, exdates }),
+        });
+    }
+
+    entries
+}
+
+/// Returns the timezone the scraped grid should be interpreted in.
+///
+/// Defaults to `Europe/Paris` (UT1 Capitole's zone); overridable with the
+/// `TIMEZONE` env var for other campuses or testing.
+fn configured_timezone() -> Tz {
+    var("TIMEZONE")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(chrono_tz::Europe::Paris)
+}
+
+/// Resolves a wall-clock datetime scraped from the grid into the correct UTC
+/// instant, honouring the configured timezone's DST rules.
+///
+/// This replaces the old fixed `- 1 hour` fudge, which silently broke twice a
+/// year across the Europe/Paris DST boundary.
+fn local_to_utc(naive: chrono::naive::NaiveDateTime) -> DateTime<Utc> {
+    let tz = configured_timezone();
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => dt.with_timezone(&Utc),
+        // autumn fall-back: the wall-clock time happens twice, pick the first
+        LocalResult::Ambiguous(dt, _) => dt.with_timezone(&Utc),
+        // spring-forward gap: the wall-clock time does not exist. Advance past
+        // the transition and re-resolve the shifted time *through the zone* so
+        // the result carries the correct (post-gap) offset — calling `and_utc()`
+        // on a local value would be wrong by the whole zone offset.
+        LocalResult::None => match tz.from_local_datetime(&(naive + Duration::hours(1))) {
+            LocalResult::Single(dt) => dt.with_timezone(&Utc),
+            LocalResult::Ambiguous(dt, _) => dt.with_timezone(&Utc),
+            LocalResult::None => (naive + Duration::hours(1)).and_utc(),
+        },
+    }
+}
+
+/// Formats a wall-clock datetime as a UTC ICS timestamp (`...Z`).
+fn ics_utc(naive: chrono::naive::NaiveDateTime) -> String {
+    local_to_utc(naive).format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Formats a wall-clock datetime as a floating local ICS timestamp (no `Z`),
+/// meant to be paired with a `TZID` parameter so the client expands it through
+/// the `VTIMEZONE` rules instead of a fixed UTC anchor.
+fn ics_local(naive: chrono::naive::NaiveDateTime) -> String {
+    naive.format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// Builds the `VTIMEZONE` block for the configured zone so that `TZID`-qualified
+/// recurring events expand with the correct DST offset on the subscriber's end.
+///
+/// Only Europe/Paris (UT1 Capitole's zone) is described. Returns `None` for any
+/// other zone so the caller can refuse to emit `TZID=<zone>` events with no
+/// matching definition, rather than silently leaning on the client's database.
+fn build_vtimezone(tz: Tz) -> Option<IcsTimeZone<'static>> {
+    if tz != chrono_tz::Europe::Paris {
+        return None;
+    }
+
+    let mut standard = Standard::new("19701025T030000", "+0200", "+0100");
+    standard.push(TzName::new("CET"));
+    standard.push(RRule::new("FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU"));
+
+    let mut daylight = Daylight::new("19700329T020000", "+0100", "+0200");
+    daylight.push(TzName::new("CEST"));
+    daylight.push(RRule::new("FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU"));
+
+    let mut vtimezone = IcsTimeZone::standard(tz.name(), standard);
+    vtimezone.add_daylight(daylight);
+    Some(vtimezone)
+}
+
+async fn create_ics_from_planning_event_vec<'a>(
+    events: &Vec<PlanningEvent>,
+    output: &'a str,
+) -> Result<&'a str> {
     println!("Creating ics file from merged events");
 
+    // fold repeated weekly lectures into recurring entries before generation
+    let entries = fold_recurring_events(events);
+
     // create ics calendar
     let mut calendar = ICalendar::new(
         "2.0",
         "https://www.github.com/loouis-t/ut1-timetable",
     );
 
-    let mut threads = Vec::new();
-    for event in events.clone() {
-        let thread = tokio::spawn(async move {
-            // create random uid
-            let uid = format!("{}", random::<i64>());
-
-            // create ics event
-            let mut ics_event = Event::new(
-                uid,
-                Utc::now().format("%Y%m%dT%H%M%SZ").to_string(),
-            );
-            ics_event.push(DtStart::new(
-                event.start.format("%Y%m%dT%H%M%SZ").to_string())
-            );
-            ics_event.push(DtEnd::new(
-                (event.start + event.duration_s)
-                    .format("%Y%m%dT%H%M%SZ").to_string())
-            );
-            ics_event.push(Summary::new(event.cours));
-            ics_event.push(Location::new(event.salle));
-            ics_event.push(Organizer::new(event.prof));
-            ics_event.push(Description::new(event.notes));
-
-            // return event
-            ics_event
-        });
-        threads.push(thread);
+    // recurring events are anchored to local time + TZID, so the calendar needs
+    // a VTIMEZONE describing the zone's DST transitions. Refuse to emit TZID
+    // events for a zone we can't describe rather than shipping dangling refs.
+    let timezone = configured_timezone();
+    if entries.iter().any(|e| e.recurrence.is_some()) {
+        let vtimezone = build_vtimezone(timezone).ok_or_else(|| anyhow!(
+            "No VTIMEZONE definition available for timezone '{}'; \
+             set TIMEZONE=Europe/Paris or add support for this zone",
+            timezone.name(),
+        ))?;
+        calendar.add_timezone(vtimezone);
     }
 
-    for thread in threads {
-        calendar.add_event(thread.await?);
+    // load the previous run's sync state so unchanged events keep their
+    // DTSTAMP/SEQUENCE (see EventState)
+    let previous_state = load_event_state();
+    let mut next_state = HashMap::new();
+    let now = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    for entry in entries {
+        let CalendarEntry { event, recurrence } = entry;
+
+        // deterministic UID from the event's stable identity
+        let uid = event_uid(&event, recurrence.is_some());
+        // content hash decides whether this event actually changed since the
+        // last run; the RRULE window is part of the content so a new UNTIL or
+        // EXDATE correctly bumps the sequence.
+        let content_hash = event_content_hash(&event, recurrence.as_ref());
+
+        let (sequence, dtstamp) = match previous_state.get(&uid) {
+            Some(prev) if prev.content_hash == content_hash => {
+                // unchanged: reuse the previous stamp, do not churn subscribers
+                (prev.sequence, prev.dtstamp.clone())
+            }
+            Some(prev) => (prev.sequence + 1, now.clone()),
+            None => (0, now.clone()),
+        };
+
+        next_state.insert(uid.clone(), EventState {
+            content_hash,
+            sequence,
+            dtstamp: dtstamp.clone(),
+        });
+
+        // split out the structured bits before the fields are moved into the
+        // VEVENT: each professor becomes a chair ATTENDEE and the lecture type
+        // (CM/TD/TP) becomes CATEGORIES.
+        let professors = split_professors(&event.prof);
+        let categories = lecture_categories(&event);
+
+        let mut ics_event = Event::new(uid, dtstamp);
+        ics_event.push(Sequence::new(sequence.to_string()));
+        // recurring entries use local time + TZID so weekly occurrences follow
+        // the zone's DST rules; one-offs stay as fixed UTC instants.
+        if recurrence.is_some() {
+            let tzid = timezone.name();
+            let mut dtstart = DtStart::new(ics_local(event.start));
+            dtstart.append(parameters!("TZID" => tzid));
+            ics_event.push(dtstart);
+            let mut dtend = DtEnd::new(ics_local(event.start + event.duration_s));
+            dtend.append(parameters!("TZID" => tzid));
+            ics_event.push(dtend);
+        } else {
+            ics_event.push(DtStart::new(ics_utc(event.start)));
+            ics_event.push(DtEnd::new(ics_utc(event.start + event.duration_s)));
+        }
+        ics_event.push(Summary::new(event.cours));
+        ics_event.push(Location::new(event.salle));
+
+        // the organizer is the scraper/service, not a professor
+        ics_event.push(Organizer::new(ORGANIZER_CAL_ADDRESS));
+
+        // professors are chairpersons attending the lecture
+        for prof in professors {
+            let mut attendee = Attendee::new(format!("mailto:{}", slugify_prof(&prof)));
+            attendee.append(parameters!(
+                "CUTYPE" => "INDIVIDUAL";
+                "ROLE" => "CHAIR";
+                "PARTSTAT" => "ACCEPTED";
+                "CN" => prof.clone()
+            ));
+            ics_event.push(attendee);
+        }
+
+        push_categories(&mut ics_event, &categories);
+
+        // leftover free-text fragments stay as a COMMENT rather than a
+        // newline-flattened DESCRIPTION
+        if !event.notes.trim().is_empty() {
+            ics_event.push(Comment::new(event.notes));
+        }
+
+        // fold weekly repeats into a single RRULE + EXDATE set. UNTIL stays UTC
+        // (RFC5545 requires it when DTSTART carries a TZID); the EXDATEs are
+        // encoded exactly like DTSTART — local + TZID — so they line up with the
+        // occurrences the client expands and actually cancel the missing weeks.
+        if let Some(recurrence) = recurrence {
+            let tzid = timezone.name();
+            ics_event.push(RRule::new(format!(
+                "FREQ=WEEKLY;UNTIL={}",
+                ics_utc(recurrence.until),
+            )));
+            for exdate in recurrence.exdates {
+                let mut ex = ExDate::new(ics_local(exdate));
+                ex.append(parameters!("TZID" => tzid));
+                ics_event.push(ex);
+            }
+        }
+
+        calendar.add_event(ics_event);
     }
 
+    // persist the new sync state next to the calendar
+    save_event_state(&next_state);
+
     // Save ics file in directory
-    calendar.save_file("ut1.ics")?;
+    calendar.save_file(output)?;
+
+    Ok(output)
+}
 
-    Ok("ICS saved in directory")
+/// Deterministic, stable UID for an event derived from its identity (course,
+/// room and professor), so the same lecture keeps the same UID across runs
+/// instead of being deleted and re-added every scrape.
+///
+/// A one-off is pinned to its absolute start instant. A recurring series is
+/// pinned to its *series identity* (weekday, time-of-day and duration) instead
+/// — the same key `fold_recurring_events` groups by — because its first
+/// occurrence slides forward as the scrape window rolls, and hashing that
+/// instant would change the UID (and churn the whole series) every week.
+fn event_uid(event: &PlanningEvent, recurring: bool) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(event.cours.as_bytes());
+    hasher.update([0]);
+    hasher.update(event.salle.as_bytes());
+    hasher.update([0]);
+    hasher.update(event.prof.as_bytes());
+    hasher.update([0]);
+    if recurring {
+        hasher.update(event.start.weekday().to_string().as_bytes());
+        hasher.update([0]);
+        hasher.update(event.start.time().to_string().as_bytes());
+        hasher.update([0]);
+        hasher.update(event.duration_s.num_seconds().to_string().as_bytes());
+    } else {
+        hasher.update(local_to_utc(event.start).to_rfc3339().as_bytes());
+    }
+    format!("{:x}@ut1-timetable", hasher.finalize())
+}
+
+/// Hash of the event's rendered content, used to detect whether an event's
+/// content changed between runs (and hence whether to bump SEQUENCE). For a
+/// recurring series the DTSTART/DTEND of the first scraped occurrence is not
+/// hashed; its weekday, time-of-day, duration, UNTIL and EXDATEs are.
+fn event_content_hash(event: &PlanningEvent, recurrence: Option<&Recurrence>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(event.cours.as_bytes());
+    hasher.update([0]);
+    hasher.update(event.salle.as_bytes());
+    hasher.update([0]);
+    hasher.update(event.prof.as_bytes());
+    hasher.update([0]);
+    hasher.update(event.notes.as_bytes());
+    hasher.update([0]);
+    if let Some(recurrence) = recurrence {
+        // recurring: hash the stable series identity and the RRULE window, not
+        // the first *scraped* occurrence — that anchor slides forward as the
+        // rolling window drops past weeks and would churn SEQUENCE/DTSTAMP every
+        // rollover even when the lecture is unchanged.
+        hasher.update(event.start.weekday().to_string().as_bytes());
+        hasher.update([0]);
+        hasher.update(event.start.time().to_string().as_bytes());
+        hasher.update([0]);
+        hasher.update(event.duration_s.num_seconds().to_string().as_bytes());
+        hasher.update([0]);
+        hasher.update(ics_utc(recurrence.until).as_bytes());
+        for exdate in &recurrence.exdates {
+            hasher.update([0]);
+            hasher.update(ics_utc(*exdate).as_bytes());
+        }
+    } else {
+        hasher.update(ics_utc(event.start).as_bytes());
+        hasher.update([0]);
+        hasher.update(ics_utc(event.start + event.duration_s).as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Splits the scraped professor fragment into individual names. The planning
+/// grid lists several teachers separated by newlines, commas, slashes or
+/// semicolons.
+fn split_professors(raw: &str) -> Vec<String> {
+    raw.split(['\n', ',', '/', ';'])
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Turns a professor name into a stable local-part for a `mailto:` address
+/// (the grid never exposes real e-mail addresses).
+fn slugify_prof(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '.' })
+        .collect::<String>()
+        .trim_matches('.')
+        .to_string()
+}
+
+/// Detects the lecture type(s) (CM/TD/TP) mentioned in the scraped course
+/// name, notes or room, used to populate CATEGORIES.
+fn lecture_categories(event: &PlanningEvent) -> Vec<String> {
+    let haystack = format!("{} {} {}", event.cours, event.notes, event.salle).to_uppercase();
+    let mut categories = Vec::new();
+    for kind in ["CM", "TD", "TP"] {
+        if haystack.split(|c: char| !c.is_ascii_alphanumeric())
+            .any(|token| token == kind)
+        {
+            categories.push(kind.to_string());
+        }
+    }
+    categories
+}
+
+/// Pushes the lecture categories onto the event, honouring the
+/// `CATEGORIES_MODE` env toggle: `merge` (default) emits a single CATEGORIES
+/// line with comma-separated values, `separate` emits one line per value.
+fn push_categories(ics_event: &mut Event, categories: &[String]) {
+    if categories.is_empty() {
+        return;
+    }
+    let separate = var("CATEGORIES_MODE")
+        .map(|m| m.eq_ignore_ascii_case("separate"))
+        .unwrap_or(false);
+    if separate {
+        for category in categories {
+            ics_event.push(Categories::new(category.clone()));
+        }
+    } else {
+        ics_event.push(Categories::new(categories.join(",")));
+    }
+}
+
+/// Loads the previous run's sync state, or an empty map on first run / parse
+/// failure (a corrupt state file makes every event start over at SEQUENCE 0
+/// with a fresh DTSTAMP once, never crashes).
+fn load_event_state() -> HashMap<String, EventState> {
+    std::fs::read_to_string(STATE_FILE)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Persists the sync state next to `ut1.ics`. Best-effort: a write failure is
+/// logged but must not abort a scrape.
+fn save_event_state(state: &HashMap<String, EventState>) {
+    match serde_json::to_string_pretty(state) {
+        Ok(raw) => {
+            if let Err(e) = std::fs::write(STATE_FILE, raw) {
+                println!("INFO: failed to persist event state: {}", e);
+            }
+        }
+        Err(e) => println!("INFO: failed to serialize event state: {}", e),
+    }
 }
 
 async fn deploy_ics_file() -> Result<&'static str> {
     println!("Deploying ics file");
+    let ics_dest = var("PATH_TO_DEPLOY_ICS")?;
+    // the HTML view is shipped next to the calendar
+    let html_dest = sibling_path(&ics_dest, html_calendar::HTML_FILE);
+
     if var("PROD")? == "true".to_string() {
-        // scp ics file to server
+        let server_ip = var("SERVER_IP")?;
+        // scp ics + html files to server
         std::process::Command::new("scp")
             .arg("ut1.ics")
-            .arg(format!(
-                "{}:{}",
-                var("SERVER_IP")?,
-                var("PATH_TO_DEPLOY_ICS")?
-            ))
+            .arg(format!("{}:{}", server_ip, ics_dest))
+            .spawn()?;
+        std::process::Command::new("scp")
+            .arg(html_calendar::HTML_FILE)
+            .arg(format!("{}:{}", server_ip, html_dest))
             .spawn()?;
     } else {
-        match std::fs::copy("ut1.ics", var("PATH_TO_DEPLOY_ICS")?) {
-            Ok(_) => {},
+        match std::fs::copy("ut1.ics", &ics_dest) {
+            Ok(_) => {
+                let _ = std::fs::copy(html_calendar::HTML_FILE, &html_dest);
+            },
             Err(_) => println!("INFO: Running inside docker container, ics file not copied"),
         }
     }
@@ -455,6 +1037,15 @@ async fn deploy_ics_file() -> Result<&'static str> {
     Ok("ICS deployed")
 }
 
+/// Given the deploy destination of `ut1.ics`, returns the path to a sibling
+/// file (e.g. the HTML view) in the same directory.
+fn sibling_path(ics_dest: &str, filename: &str) -> String {
+    match ics_dest.rsplit_once('/') {
+        Some((dir, _)) => format!("{}/{}", dir, filename),
+        None => filename.to_string(),
+    }
+}
+
 // just converts string to i32 and removes "px;" if present
 fn parse_int(s: &str) -> i32 {
     if s.contains("px") {
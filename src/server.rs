@@ -0,0 +1,207 @@
+/*
+ * Copyright (c) 2024. All rights reserved.
+ * This software is the confidential and proprietary information of Louis Travaux ("Confidential Information").
+ * You shall not disclose such Confidential Information and shall use it only in accordance with the terms of the license agreement you entered into with Louis Travaux.
+ */
+
+//! Optional subscription server.
+//!
+//! Instead of only `scp`-ing `ut1.ics` to an external web server, the binary
+//! can keep the latest generated calendar in memory and expose it directly over
+//! HTTP (and a minimal CalDAV surface) so clients subscribe to it. Enabled with
+//! the `serve` feature and driven by the `SERVE_ADDR` env var; the 6-hour
+//! scrape loop refreshes the served document in place via [`update`].
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+
+/// The calendar document currently being served, together with the validators
+/// clients use for conditional requests.
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    pub body: String,
+    /// Strong `ETag`, a hash of the deterministic event set.
+    pub etag: String,
+    /// `Last-Modified` in HTTP date format.
+    pub last_modified: String,
+}
+
+/// Shared handle to the served document, cloned into the scrape loop and the
+/// server task.
+pub type ServedCalendar = Arc<RwLock<Document>>;
+
+/// Path the calendar is exposed at (also the CalDAV collection resource).
+const CALENDAR_PATH: &str = "/ut1.ics";
+
+/// Creates an empty shared calendar handle.
+pub fn shared() -> ServedCalendar {
+    Arc::new(RwLock::new(Document::default()))
+}
+
+/// Replaces the in-memory calendar with freshly generated ICS content.
+///
+/// The `ETag` is a hash of the body itself: because the underlying event set is
+/// now deterministic (stable UIDs, stable DTSTAMP), an unchanged timetable
+/// yields an unchanged `ETag`, so subscribers get a clean `304 Not Modified`.
+pub async fn update(calendar: &ServedCalendar, body: String) {
+    let etag = format!("\"{:x}\"", Sha256::digest(body.as_bytes()));
+    let last_modified = Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+    let mut doc = calendar.write().await;
+    *doc = Document { body, etag, last_modified };
+}
+
+/// Runs the HTTP/CalDAV server until the process exits.
+pub async fn serve(calendar: ServedCalendar, addr: SocketAddr) -> Result<()> {
+    let make_service = make_service_fn(move |_conn| {
+        let calendar = calendar.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                handle(req, calendar.clone())
+            }))
+        }
+    });
+
+    println!("Serving calendar on http://{}{}", addr, CALENDAR_PATH);
+    Server::try_bind(&addr)
+        .context("Failed to bind SERVE_ADDR")?
+        .serve(make_service)
+        .await
+        .context("Server error")?;
+
+    Ok(())
+}
+
+async fn handle(req: Request<Body>, calendar: ServedCalendar) -> Result<Response<Body>, Infallible> {
+    let doc = calendar.read().await.clone();
+    let response = match *req.method() {
+        Method::GET | Method::HEAD => get_calendar(&req, &doc),
+        Method::OPTIONS => options(),
+        // CalDAV verbs arrive as extension methods
+        _ => match req.method().as_str() {
+            "PROPFIND" => propfind(&doc),
+            "REPORT" => report(&doc),
+            _ => Response::builder()
+                .status(StatusCode::METHOD_NOT_ALLOWED)
+                .body(Body::empty())
+                .unwrap(),
+        },
+    };
+    Ok(response)
+}
+
+/// `GET`/`HEAD` of the calendar with conditional-request support.
+fn get_calendar(req: &Request<Body>, doc: &Document) -> Response<Body> {
+    if doc.body.is_empty() {
+        return Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .body(Body::from("Calendar not generated yet"))
+            .unwrap();
+    }
+
+    // honour If-None-Match so unchanged calendars short-circuit to 304
+    if let Some(inm) = req.headers().get(hyper::header::IF_NONE_MATCH) {
+        if inm.to_str().map(|v| v.contains(&doc.etag)).unwrap_or(false) {
+            return Response::builder()
+                .status(StatusCode::NOT_MODIFIED)
+                .header(hyper::header::ETAG, doc.etag.clone())
+                .body(Body::empty())
+                .unwrap();
+        }
+    }
+
+    let body = if req.method() == Method::HEAD {
+        Body::empty()
+    } else {
+        Body::from(doc.body.clone())
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(hyper::header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+        .header(hyper::header::ETAG, doc.etag.clone())
+        .header(hyper::header::LAST_MODIFIED, doc.last_modified.clone())
+        .body(body)
+        .unwrap()
+}
+
+/// Advertises the CalDAV capabilities of the collection.
+fn options() -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("DAV", "1, 3, calendar-access")
+        .header(hyper::header::ALLOW, "OPTIONS, GET, HEAD, PROPFIND, REPORT")
+        .body(Body::empty())
+        .unwrap()
+}
+
+/// Minimal `PROPFIND` returning the collection's validators so CalDAV clients
+/// can detect changes without downloading the whole calendar.
+fn propfind(doc: &Document) -> Response<Body> {
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<multistatus xmlns="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <response>
+    <href>{path}</href>
+    <propstat>
+      <prop>
+        <resourcetype><collection/><C:calendar/></resourcetype>
+        <getcontenttype>text/calendar; charset=utf-8</getcontenttype>
+        <getetag>{etag}</getetag>
+        <getlastmodified>{last_modified}</getlastmodified>
+      </prop>
+      <status>HTTP/1.1 200 OK</status>
+    </propstat>
+  </response>
+</multistatus>"#,
+        path = CALENDAR_PATH,
+        etag = doc.etag,
+        last_modified = doc.last_modified,
+    );
+    multistatus(xml)
+}
+
+/// Minimal calendaring `REPORT` returning the calendar data inline.
+fn report(doc: &Document) -> Response<Body> {
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<multistatus xmlns="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <response>
+    <href>{path}</href>
+    <propstat>
+      <prop>
+        <getetag>{etag}</getetag>
+        <C:calendar-data>{data}</C:calendar-data>
+      </prop>
+      <status>HTTP/1.1 200 OK</status>
+    </propstat>
+  </response>
+</multistatus>"#,
+        path = CALENDAR_PATH,
+        etag = doc.etag,
+        data = xml_escape(&doc.body),
+    );
+    multistatus(xml)
+}
+
+fn multistatus(xml: String) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::from_u16(207).unwrap())
+        .header(hyper::header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(xml))
+        .unwrap()
+}
+
+fn xml_escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}